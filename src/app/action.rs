@@ -0,0 +1,15 @@
+/// The kind of command-input operation currently in progress for the task view.
+///
+/// `View` means no input is being collected; the other variants tell
+/// `Hourglass::update_command_input` what to do with the typed text once the
+/// user presses Enter.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    View,
+    Add,
+    Update,
+    Track,
+    Tag,
+    Filter,
+    Dependency,
+}
@@ -5,18 +5,24 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use serde::{Deserialize, Serialize};
-use serde_json;
-use std::fs;
+use std::collections::HashSet;
+use std::path::PathBuf;
 use std::{
-    env, io,
+    io,
     time::{Duration, Instant},
 };
 use tui::{backend::CrosstermBackend, widgets::TableState, Terminal};
 
 mod action;
+mod priority;
+mod storage;
+mod time_entry;
 mod ui;
 
+use crate::cli::Command;
 use action::Action;
+use priority::Priority;
+use time_entry::TimeEntry;
 
 mod date_format_for_serde {
     use chrono::{DateTime, Utc};
@@ -62,8 +68,6 @@ enum View {
     Issues,
 }
 
-pub const HOURGLASS_EXTENSION: &str = "hourglass";
-pub const HOURGLASS_FILE_STORAGE_NAME: &str = "tasks.hourglass";
 pub const TIME_FORMAT: &'static str = "%b %d, %Y %I:%M %p";
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -75,6 +79,28 @@ struct Task {
     created_at: DateTime<Utc>,
     #[serde(with = "date_format_for_serde")]
     modified_at: DateTime<Utc>,
+    #[serde(default)]
+    time_entries: Vec<TimeEntry>,
+    #[serde(default)]
+    priority: Priority,
+    #[serde(default)]
+    tags: HashSet<String>,
+    #[serde(default)]
+    dependencies: HashSet<i32>,
+}
+
+impl Task {
+    fn total_time_logged(&self) -> time_entry::Duration {
+        let mut total = time_entry::Duration::new(0, 0);
+
+        for entry in &self.time_entries {
+            total.hours += entry.duration.hours;
+            total.minutes += entry.duration.minutes;
+        }
+
+        total.normalize();
+        total
+    }
 }
 
 pub struct Hourglass {
@@ -87,6 +113,10 @@ pub struct Hourglass {
     table_state: TableState,
 
     tasks: Vec<Task>,
+
+    tag_filter: Option<String>,
+
+    file_path: PathBuf,
 }
 
 impl Hourglass {
@@ -98,6 +128,8 @@ impl Hourglass {
             next_id: 1,
             tasks: vec![],
             table_state: TableState::default(),
+            tag_filter: None,
+            file_path: PathBuf::from(storage::DEFAULT_FILE_NAME),
         }
     }
 
@@ -154,10 +186,105 @@ impl Hourglass {
         }
     }
 
+    /// Runs a single CLI subcommand directly against the loaded tasks,
+    /// printing the result, and persisting any change before returning.
+    pub fn run_command(&mut self, command: Command) -> io::Result<()> {
+        match command {
+            Command::Add { description } => {
+                let id = self.add_task(description);
+                self.save_tasks()?;
+                println!("Added task {}", id);
+            }
+            Command::List => {
+                for task in &self.tasks {
+                    println!(
+                        "[{}] {} {} ({})",
+                        task.id,
+                        if task.completed { "x" } else { " " },
+                        task.description,
+                        task.priority
+                    );
+                }
+            }
+            Command::Done { id } => {
+                if !self.tasks.iter().any(|task| task.id == id) {
+                    eprintln!("No task with id {}", id);
+                } else if self.is_blocked(id) {
+                    eprintln!("Task {} is blocked by incomplete dependencies", id);
+                } else {
+                    if let Some(task) = self.tasks.iter_mut().find(|task| task.id == id) {
+                        task.completed = true;
+                        task.modified_at = Utc::now();
+                    }
+                    self.save_tasks()?;
+                    println!("Completed task {}", id);
+                }
+            }
+            Command::Rm { id } => {
+                if self.remove_task(id) {
+                    self.save_tasks()?;
+                    println!("Removed task {}", id);
+                } else {
+                    eprintln!("No task with id {}", id);
+                }
+            }
+            Command::Track { id, duration } => match time_entry::Duration::parse(&duration) {
+                Some(duration) => {
+                    if self.track_time(id, duration) {
+                        self.save_tasks()?;
+                        println!("Logged {} on task {}", duration, id);
+                    } else {
+                        eprintln!("No task with id {}", id);
+                    }
+                }
+                None => eprintln!("Invalid duration: {}", duration),
+            },
+        }
+
+        Ok(())
+    }
+
+    // the table only ever shows the tasks that pass `tag_filter`, so a row
+    // index from `table_state` does not necessarily match the index of the
+    // corresponding task in `self.tasks` - this maps displayed row -> raw index
+    fn visible_task_indices(&self) -> Vec<usize> {
+        match &self.tag_filter {
+            Some(tag) => self
+                .tasks
+                .iter()
+                .enumerate()
+                .filter(|(_, task)| task.tags.contains(tag))
+                .map(|(i, _)| i)
+                .collect(),
+            None => (0..self.tasks.len()).collect(),
+        }
+    }
+
+    fn selected_task_index(&self) -> Option<usize> {
+        let indices = self.visible_task_indices();
+
+        self.table_state
+            .selected()
+            .and_then(|row| indices.get(row).copied())
+    }
+
+    fn selected_task_id(&self) -> Option<i32> {
+        self.selected_task_index()
+            .and_then(|i| self.tasks.get(i))
+            .map(|task| task.id)
+    }
+
     fn next(&mut self) {
+        let len = self.visible_task_indices().len();
+
+        if len == 0 {
+            self.table_state.select(None);
+            return;
+        }
+
         let i = match self.table_state.selected() {
             Some(i) => {
-                if i >= self.tasks.len() - 1 {
+                if i >= len - 1 {
                     0
                 } else {
                     i + 1
@@ -170,10 +297,17 @@ impl Hourglass {
     }
 
     fn previous(&mut self) {
+        let len = self.visible_task_indices().len();
+
+        if len == 0 {
+            self.table_state.select(None);
+            return;
+        }
+
         let i = match self.table_state.selected() {
             Some(i) => {
                 if i == 0 {
-                    self.tasks.len() - 1
+                    len - 1
                 } else {
                     i - 1
                 }
@@ -183,54 +317,283 @@ impl Hourglass {
         self.table_state.select(Some(i));
     }
 
-    fn toggle_task_status(&mut self) {
-        if let Some(i) = self.table_state.selected() {
-            if let Some(task) = self.tasks.get_mut(i) {
-                task.completed = !task.completed;
-            }
-        }
-    }
+    // core mutations below are driven by task id so both the TUI (which
+    // resolves the selected row to an id first) and the CLI (which takes an
+    // id directly as an argument) can share them
 
-    fn add_task(&mut self) {
-        let description = self.input.clone();
+    fn add_task(&mut self, description: String) -> i32 {
+        let id = self.next_id;
         let time = Utc::now();
 
-        self.input = String::new();
-
         self.tasks.push(Task {
-            id: self.next_id,
+            id,
             description,
             completed: false,
             created_at: time,
             modified_at: time,
+            time_entries: vec![],
+            priority: Priority::default(),
+            tags: HashSet::new(),
+            dependencies: HashSet::new(),
         });
 
         self.next_id += 1;
 
-        self.save_tasks();
+        id
+    }
+
+    fn remove_task(&mut self, id: i32) -> bool {
+        match self.tasks.iter().position(|task| task.id == id) {
+            Some(index) => {
+                self.tasks.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // a task cannot be marked complete while any of its dependencies are
+    // still incomplete; un-completing it is always allowed
+    fn is_blocked(&self, id: i32) -> bool {
+        match self.tasks.iter().find(|task| task.id == id) {
+            Some(task) => task.dependencies.iter().any(|dep_id| {
+                self.tasks
+                    .iter()
+                    .find(|task| task.id == *dep_id)
+                    .map_or(false, |dep| !dep.completed)
+            }),
+            None => false,
+        }
+    }
+
+    fn blocking_descriptions(&self, id: i32) -> Vec<String> {
+        match self.tasks.iter().find(|task| task.id == id) {
+            Some(task) => task
+                .dependencies
+                .iter()
+                .filter_map(|dep_id| self.tasks.iter().find(|task| task.id == *dep_id))
+                .filter(|dep| !dep.completed)
+                .map(|dep| dep.description.clone())
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    // whether `to` is reachable from `from` by following dependency edges -
+    // used to reject an edge that would introduce a cycle
+    fn depends_on_transitively(&self, from: i32, to: i32) -> bool {
+        let mut stack = vec![from];
+        let mut visited = HashSet::new();
+
+        while let Some(current) = stack.pop() {
+            if current == to {
+                return true;
+            }
+
+            if !visited.insert(current) {
+                continue;
+            }
+
+            if let Some(task) = self.tasks.iter().find(|task| task.id == current) {
+                stack.extend(task.dependencies.iter().copied());
+            }
+        }
+
+        false
+    }
+
+    fn toggle_task_status(&mut self, id: i32) -> bool {
+        let blocked = self.is_blocked(id);
+
+        match self.tasks.iter_mut().find(|task| task.id == id) {
+            Some(task) if task.completed => {
+                task.completed = false;
+                task.modified_at = Utc::now();
+                true
+            }
+            Some(task) if !blocked => {
+                task.completed = true;
+                task.modified_at = Utc::now();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // toggles a dependency edge `selected task -> dep_id`: removes it if it
+    // already exists, otherwise adds it unless doing so would introduce a
+    // cycle
+    fn toggle_task_dependency(&mut self, selected_id: i32, dep_id: i32) -> bool {
+        if selected_id == dep_id || !self.tasks.iter().any(|task| task.id == dep_id) {
+            return false;
+        }
+
+        let removed = self
+            .tasks
+            .iter_mut()
+            .find(|task| task.id == selected_id)
+            .map_or(false, |task| task.dependencies.remove(&dep_id));
+
+        if !removed {
+            if self.depends_on_transitively(dep_id, selected_id) {
+                return false;
+            }
+
+            if let Some(task) = self.tasks.iter_mut().find(|task| task.id == selected_id) {
+                task.dependencies.insert(dep_id);
+            }
+        }
+
+        if let Some(task) = self.tasks.iter_mut().find(|task| task.id == selected_id) {
+            task.modified_at = Utc::now();
+        }
+
+        true
+    }
+
+    fn track_time(&mut self, id: i32, duration: time_entry::Duration) -> bool {
+        match self.tasks.iter_mut().find(|task| task.id == id) {
+            Some(task) => {
+                task.time_entries.push(TimeEntry {
+                    logged_date: Utc::now().date_naive(),
+                    duration,
+                });
+
+                task.modified_at = Utc::now();
+                true
+            }
+            None => false,
+        }
+    }
+
+    // TUI-facing wrappers: resolve the selected row to an id/description via
+    // `self.input` or `self.table_state`, then delegate to the id-driven
+    // mutations above
+
+    fn add_task_from_input(&mut self) {
+        let description = self.input.clone();
+        self.input = String::new();
+
+        self.add_task(description);
+
+        self.save_tasks().expect("Unable to save tasks");
     }
 
     fn update_task(&mut self) {
-        if let Some(i) = self.table_state.selected() {
+        if let Some(i) = self.selected_task_index() {
             if let Some(task) = self.tasks.get_mut(i) {
                 task.description = self.input.clone();
 
                 task.modified_at = Utc::now();
 
-                self.save_tasks();
+                self.save_tasks().expect("Unable to save tasks");
             }
         }
 
         self.input = String::new();
     }
 
-    fn remove_task(&mut self) {
-        if let Some(index) = self.table_state.selected() {
-            self.tasks.remove(index);
-            self.save_tasks();
+    fn remove_selected_task(&mut self) {
+        if let Some(id) = self.selected_task_id() {
+            self.remove_task(id);
+            self.save_tasks().expect("Unable to save tasks");
+        }
+    }
+
+    fn toggle_selected_task_status(&mut self) {
+        if let Some(id) = self.selected_task_id() {
+            if self.toggle_task_status(id) {
+                self.save_tasks().expect("Unable to save tasks");
+            }
+        }
+    }
+
+    fn track_time_from_input(&mut self) {
+        if let Some(id) = self.selected_task_id() {
+            if let Some(duration) = time_entry::Duration::parse(&self.input) {
+                self.track_time(id, duration);
+                self.save_tasks().expect("Unable to save tasks");
+            }
+        }
+
+        self.input = String::new();
+    }
+
+    fn toggle_dependency_from_input(&mut self) {
+        let input = self.input.trim().to_string();
+        self.input = String::new();
+
+        if let (Some(selected_id), Ok(dep_id)) = (self.selected_task_id(), input.parse::<i32>()) {
+            if self.toggle_task_dependency(selected_id, dep_id) {
+                self.save_tasks().expect("Unable to save tasks");
+            }
+        }
+    }
+
+    fn cycle_task_priority(&mut self) {
+        if let Some(i) = self.selected_task_index() {
+            if let Some(task) = self.tasks.get_mut(i) {
+                task.priority = task.priority.cycle();
+                task.modified_at = Utc::now();
+
+                self.save_tasks().expect("Unable to save tasks");
+            }
+        }
+    }
+
+    fn sort_tasks_by_priority(&mut self) {
+        self.tasks.sort_by(|a, b| b.priority.cmp(&a.priority));
+
+        if !self.visible_task_indices().is_empty() {
+            self.table_state.select(Some(0));
         }
     }
 
+    // toggles `self.input` as a tag on the selected task: attaches it if
+    // absent, detaches it if already present
+    fn toggle_task_tag(&mut self) {
+        let tag = self.input.trim().to_string();
+
+        if !tag.is_empty() {
+            if let Some(i) = self.selected_task_index() {
+                if let Some(task) = self.tasks.get_mut(i) {
+                    if !task.tags.remove(&tag) {
+                        task.tags.insert(tag);
+                    }
+
+                    task.modified_at = Utc::now();
+
+                    self.save_tasks().expect("Unable to save tasks");
+                }
+            }
+        }
+
+        self.input = String::new();
+    }
+
+    fn apply_tag_filter(&mut self) {
+        let query = self.input.trim().to_string();
+
+        self.tag_filter = if query.is_empty() { None } else { Some(query) };
+        self.input = String::new();
+
+        self.table_state.select(if self.visible_task_indices().is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+    }
+
+    fn clear_tag_filter(&mut self) {
+        self.tag_filter = None;
+
+        self.table_state.select(if self.tasks.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+    }
+
     fn handle_input(&mut self, key_event: KeyEvent) {
         // we handle input differently based on the current view
         match &self.view {
@@ -249,7 +612,7 @@ impl Hourglass {
             KeyCode::Enter => match &self.view {
                 View::Task(action) => match action {
                     Action::Add => {
-                        self.add_task();
+                        self.add_task_from_input();
 
                         self.view = View::Task(Action::View)
                     }
@@ -258,6 +621,26 @@ impl Hourglass {
 
                         self.view = View::Task(Action::View)
                     }
+                    Action::Track => {
+                        self.track_time_from_input();
+
+                        self.view = View::Task(Action::View)
+                    }
+                    Action::Tag => {
+                        self.toggle_task_tag();
+
+                        self.view = View::Task(Action::View)
+                    }
+                    Action::Filter => {
+                        self.apply_tag_filter();
+
+                        self.view = View::Task(Action::View)
+                    }
+                    Action::Dependency => {
+                        self.toggle_dependency_from_input();
+
+                        self.view = View::Task(Action::View)
+                    }
                     _ => {}
                 },
 
@@ -280,10 +663,17 @@ impl Hourglass {
                 'q' => self.should_quit = true,
                 'j' => self.next(),
                 'k' => self.previous(),
-                'd' => self.toggle_task_status(),
+                'd' => self.toggle_selected_task_status(),
                 'a' => self.view = View::Task(Action::Add),
                 'u' => self.view = View::Task(Action::Update),
-                'x' => self.remove_task(),
+                't' => self.view = View::Task(Action::Track),
+                'p' => self.cycle_task_priority(),
+                's' => self.sort_tasks_by_priority(),
+                'g' => self.view = View::Task(Action::Tag),
+                'f' => self.view = View::Task(Action::Filter),
+                'F' => self.clear_tag_filter(),
+                'D' => self.view = View::Task(Action::Dependency),
+                'x' => self.remove_selected_task(),
                 _ => {}
             },
             KeyCode::Down => self.next(),
@@ -293,44 +683,101 @@ impl Hourglass {
     }
 
     pub fn load_tasks(&mut self) -> io::Result<()> {
-        // check if a .hourglass file exist
-        // if it does, load the content
-        // otherwise, create an empty .hourglass file
+        self.file_path = storage::resolve_path()?;
+        self.tasks = storage::load(&self.file_path)?;
 
-        let current_dir = env::current_dir()?;
+        // the CLI resolves `done`/`rm`/`track` by id in a fresh process each
+        // time, so `next_id` must be reseeded past every id already on disk
+        // or a freshly added task could collide with an existing one
+        self.next_id = self.tasks.iter().map(|task| task.id).max().unwrap_or(0) + 1;
 
-        let paths = fs::read_dir(current_dir).unwrap();
-        let mut file_exists = false;
+        Ok(())
+    }
 
-        for path in paths {
-            let file_path = path.unwrap().path();
+    fn save_tasks(&mut self) -> io::Result<()> {
+        // normalize every logged duration so we never persist an entry with
+        // `minutes >= 60`, which would otherwise be loaded back as an entry
+        // that breaks the `Duration` invariant; reject the write outright
+        // if an entry is still invalid afterwards rather than normalize
+        // silently writing it anyway
+        for task in self.tasks.iter_mut() {
+            for entry in task.time_entries.iter_mut() {
+                entry.duration.normalize();
+
+                if !entry.duration.is_valid() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "time entry for task {} has an invalid duration ({}m)",
+                            task.id, entry.duration.minutes
+                        ),
+                    ));
+                }
+            }
+        }
 
-            if let Some(os_extension) = file_path.extension() {
-                if let Some(extension) = os_extension.to_str() {
-                    if extension == HOURGLASS_EXTENSION {
-                        file_exists = true;
+        storage::save(&self.file_path, &self.tasks)
+    }
+}
 
-                        let content =
-                            fs::read_to_string(file_path).expect("Unable to read .hourglass file");
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-                        let datas: Vec<Task> = serde_json::from_str(&content)?;
+    #[test]
+    fn depends_on_transitively_follows_chained_edges() {
+        let mut app = Hourglass::new();
+        let a = app.add_task(String::from("a"));
+        let b = app.add_task(String::from("b"));
+        let c = app.add_task(String::from("c"));
 
-                        self.tasks = datas;
-                    }
-                }
-            }
-        }
+        assert!(app.toggle_task_dependency(a, b));
+        assert!(app.toggle_task_dependency(b, c));
 
-        if !file_exists {
-            fs::write(HOURGLASS_FILE_STORAGE_NAME, "")?;
-        }
+        assert!(app.depends_on_transitively(a, c));
+        assert!(!app.depends_on_transitively(c, a));
+    }
 
-        Ok(())
+    #[test]
+    fn toggle_task_dependency_rejects_a_cycle() {
+        let mut app = Hourglass::new();
+        let a = app.add_task(String::from("a"));
+        let b = app.add_task(String::from("b"));
+
+        assert!(app.toggle_task_dependency(a, b));
+        // b -> a would close the cycle a -> b -> a
+        assert!(!app.toggle_task_dependency(b, a));
     }
 
-    fn save_tasks(&self) {
-        let serialized = serde_json::to_string(&self.tasks).unwrap();
+    #[test]
+    fn toggle_task_dependency_removes_an_existing_edge() {
+        let mut app = Hourglass::new();
+        let a = app.add_task(String::from("a"));
+        let b = app.add_task(String::from("b"));
+
+        assert!(app.toggle_task_dependency(a, b));
+        assert!(app.toggle_task_dependency(a, b));
+
+        assert!(!app.depends_on_transitively(a, b));
+    }
+
+    #[test]
+    fn visible_task_indices_maps_filtered_rows_back_to_raw_indices() {
+        let mut app = Hourglass::new();
+        let tagged = app.add_task(String::from("tagged"));
+        let _untagged = app.add_task(String::from("untagged"));
+
+        if let Some(task) = app.tasks.iter_mut().find(|t| t.id == tagged) {
+            task.tags.insert(String::from("work"));
+        }
+
+        app.tag_filter = Some(String::from("work"));
+
+        let indices = app.visible_task_indices();
+        assert_eq!(indices.len(), 1);
+        assert_eq!(app.tasks[indices[0]].id, tagged);
 
-        fs::write(HOURGLASS_FILE_STORAGE_NAME, serialized).expect("Unable to write to file");
+        app.table_state.select(Some(0));
+        assert_eq!(app.selected_task_id(), Some(tagged));
     }
 }
@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_FILE_NAME: &str = "tasks.hourglass";
+
+const CURRENT_VERSION: u32 = 1;
+
+/// The on-disk shape of a `.hourglass` file. Keeping the task list behind a
+/// `version` means a future change to the `Task` schema can migrate old data
+/// on load instead of failing `serde_json::from_str` outright.
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    version: u32,
+    tasks: T,
+}
+
+/// Resolves where the task file lives, in priority order: the
+/// `$HOURGLASS_FILE` env var, then `<config dir>/hourglass/tasks.hourglass`,
+/// falling back to the current directory if no config dir is available.
+pub fn resolve_path() -> io::Result<PathBuf> {
+    if let Ok(path) = env::var("HOURGLASS_FILE") {
+        return Ok(PathBuf::from(path));
+    }
+
+    if let Some(mut dir) = dirs::config_dir() {
+        dir.push("hourglass");
+        fs::create_dir_all(&dir)?;
+        dir.push(DEFAULT_FILE_NAME);
+        return Ok(dir);
+    }
+
+    Ok(env::current_dir()?.join(DEFAULT_FILE_NAME))
+}
+
+/// Loads the task list from `path`, returning the default (empty) value if
+/// the file doesn't exist yet or is empty.
+pub fn load<T>(path: &Path) -> io::Result<T>
+where
+    T: Default + for<'de> Deserialize<'de>,
+{
+    if !path.exists() {
+        return Ok(T::default());
+    }
+
+    let content = fs::read_to_string(path)?;
+
+    if content.trim().is_empty() {
+        return Ok(T::default());
+    }
+
+    if let Ok(envelope) = serde_json::from_str::<Envelope<T>>(&content) {
+        return Ok(envelope.tasks);
+    }
+
+    // files written before the envelope existed stored the task list
+    // directly - fall back to reading that shape so they still load
+    serde_json::from_str::<T>(&content)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Serializes `tasks` into the current envelope and writes it atomically by
+/// writing to a temp file in the same directory and renaming it over `path`,
+/// so a crash mid-write can never leave behind a corrupt or truncated file.
+pub fn save<T>(path: &Path, tasks: &T) -> io::Result<()>
+where
+    T: Serialize,
+{
+    let envelope = Envelope {
+        version: CURRENT_VERSION,
+        tasks,
+    };
+
+    let serialized = serde_json::to_string(&envelope)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    let tmp_path = path.with_extension("hourglass.tmp");
+    fs::write(&tmp_path, serialized)?;
+    fs::rename(&tmp_path, path)?;
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A logged amount of work time, kept as separate hour/minute components
+/// rather than a single minute count so it round-trips through the `1H30M`
+/// style the user types in the command input.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct Duration {
+    pub hours: u16,
+    pub minutes: u16,
+}
+
+impl Duration {
+    pub fn new(hours: u16, minutes: u16) -> Self {
+        let mut duration = Self { hours, minutes };
+        duration.normalize();
+        duration
+    }
+
+    /// Enforces the representation invariant that `minutes < 60`, carrying
+    /// any overflow into `hours`.
+    pub fn normalize(&mut self) {
+        self.hours += self.minutes / 60;
+        self.minutes %= 60;
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.minutes < 60
+    }
+
+    /// Parses a duration like `1H30M`, `45M`, or `2H`. Returns `None` if the
+    /// input doesn't match that shape.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim().to_uppercase();
+
+        if input.is_empty() {
+            return None;
+        }
+
+        let (hours, rest) = match input.split_once('H') {
+            Some((h, rest)) => (h.parse::<u16>().ok()?, rest),
+            None => (0, input.as_str()),
+        };
+
+        let minutes = match rest {
+            "" => 0,
+            rest => rest.strip_suffix('M')?.parse::<u16>().ok()?,
+        };
+
+        Some(Self::new(hours, minutes))
+    }
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}H{:02}M", self.hours, self.minutes)
+    }
+}
+
+/// A single work session logged against a task.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct TimeEntry {
+    pub logged_date: NaiveDate,
+    pub duration: Duration,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hours_and_minutes() {
+        assert_eq!(Duration::parse("1H30M"), Some(Duration::new(1, 30)));
+        assert_eq!(Duration::parse("2H"), Some(Duration::new(2, 0)));
+        assert_eq!(Duration::parse("45M"), Some(Duration::new(0, 45)));
+        assert_eq!(Duration::parse("1h30m"), Some(Duration::new(1, 30)));
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert_eq!(Duration::parse(""), None);
+        assert_eq!(Duration::parse("nonsense"), None);
+    }
+
+    #[test]
+    fn normalize_carries_overflow_minutes_into_hours() {
+        let mut duration = Duration {
+            hours: 1,
+            minutes: 90,
+        };
+        duration.normalize();
+
+        assert_eq!(duration, Duration::new(2, 30));
+        assert!(duration.is_valid());
+    }
+
+    #[test]
+    fn new_always_produces_a_valid_duration() {
+        let duration = Duration::new(0, 125);
+
+        assert!(duration.is_valid());
+        assert_eq!(duration, Duration::new(2, 5));
+    }
+}
@@ -1,4 +1,5 @@
 use chrono::{DateTime, Local, Utc};
+use std::collections::HashSet;
 use std::time::Duration;
 use tui::{
     backend::Backend,
@@ -9,11 +10,38 @@ use tui::{
     Frame,
 };
 
-use crate::app::{Action, Hourglass, View};
+use crate::app::{Action, Hourglass, Priority, View};
 
 struct Field {
     name: String,
     value: String,
+    style: Style,
+}
+
+impl Field {
+    fn new(name: &str, value: String) -> Self {
+        Self {
+            name: String::from(name),
+            value,
+            style: Style::default().fg(Color::Red),
+        }
+    }
+
+    fn styled(name: &str, value: String, style: Style) -> Self {
+        Self {
+            name: String::from(name),
+            value,
+            style,
+        }
+    }
+}
+
+fn priority_style(priority: Priority) -> Style {
+    match priority {
+        Priority::Low => Style::default().fg(Color::Green),
+        Priority::Medium => Style::default().fg(Color::Yellow),
+        Priority::High => Style::default().fg(Color::Red),
+    }
 }
 
 pub fn build_ui<B: Backend>(f: &mut Frame<B>, app: &mut Hourglass) {
@@ -27,7 +55,7 @@ pub fn build_ui<B: Backend>(f: &mut Frame<B>, app: &mut Hourglass) {
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(rects[0]);
 
-    let header_cells = ["ID", "Description", "Age"].iter().map(|x| {
+    let header_cells = ["ID", "Description", "Priority", "Age"].iter().map(|x| {
         Cell::from(*x).style(
             Style::default()
                 .add_modifier(Modifier::UNDERLINED)
@@ -39,16 +67,18 @@ pub fn build_ui<B: Backend>(f: &mut Frame<B>, app: &mut Hourglass) {
     let header = Row::new(header_cells).style(Style::default()).height(1);
     // .bottom_margin(1);
 
-    let rows = app.tasks.iter().map(|item| {
+    let visible_indices = app.visible_task_indices();
+
+    let rows = visible_indices.iter().map(|&idx| {
+        let item = &app.tasks[idx];
         let height = 1;
 
         let cells = vec![
-            format!("{}", item.id),
-            format!("{}", item.description),
-            format_time(item.age.elapsed()),
-        ]
-        .into_iter()
-        .map(|c| Cell::from(c));
+            Cell::from(format!("{}", item.id)),
+            Cell::from(format!("{}", item.description)),
+            Cell::from(item.priority.to_string()).style(priority_style(item.priority)),
+            Cell::from(format_time(item.age.elapsed())),
+        ];
 
         let mut style = Style::default();
 
@@ -56,19 +86,27 @@ pub fn build_ui<B: Backend>(f: &mut Frame<B>, app: &mut Hourglass) {
             style = style
                 .add_modifier(Modifier::CROSSED_OUT)
                 .add_modifier(Modifier::DIM);
+        } else if app.is_blocked(item.id) {
+            style = style.fg(Color::DarkGray).add_modifier(Modifier::DIM);
         }
 
         Row::new(cells).height(height).style(style)
     });
 
+    let tasks_title = match &app.tag_filter {
+        Some(tag) => format!("Tasks - filter: {}", tag),
+        None => String::from("Tasks"),
+    };
+
     let table = Table::new(rows)
         .header(header)
-        .block(Block::default().borders(Borders::ALL).title("Tasks"))
+        .block(Block::default().borders(Borders::ALL).title(tasks_title))
         .highlight_symbol("*")
         .highlight_style(Style::default().add_modifier(Modifier::BOLD))
         .widths(&[
             Constraint::Percentage(10),
-            Constraint::Percentage(70),
+            Constraint::Percentage(55),
+            Constraint::Percentage(15),
             Constraint::Percentage(10),
         ]);
     // .column_spacing(4);
@@ -78,8 +116,12 @@ pub fn build_ui<B: Backend>(f: &mut Frame<B>, app: &mut Hourglass) {
     // display details for task selected
     let details_block = Block::default().borders(Borders::ALL);
 
-    if let Some(i) = app.table_state.selected() {
-        let selected_task = app.tasks.get(i);
+    if let Some(&idx) = app
+        .table_state
+        .selected()
+        .and_then(|row| visible_indices.get(row))
+    {
+        let selected_task = app.tasks.get(idx);
 
         if let Some(task) = selected_task {
             let time_format = "%b %d, %Y %I:%M %p";
@@ -87,26 +129,25 @@ pub fn build_ui<B: Backend>(f: &mut Frame<B>, app: &mut Hourglass) {
             let task_description_block = render_task_detail(
                 vec![String::from("Name"), String::from("Value")],
                 vec![
-                    Field {
-                        name: String::from("ID"),
-                        value: task.id.to_string(),
-                    },
-                    Field {
-                        name: String::from("Description"),
-                        value: task.description.clone(),
-                    },
-                    Field {
-                        name: String::from("Age"),
-                        value: format_time(task.age.elapsed()),
-                    },
-                    Field {
-                        name: String::from("Created at"),
-                        value: format!("{}", convert_utc_to_local(task.created_at, time_format)),
-                    },
-                    Field {
-                        name: String::from("Modified at"),
-                        value: format!("{}", convert_utc_to_local(task.modified_at, time_format)),
-                    },
+                    Field::new("ID", task.id.to_string()),
+                    Field::new("Description", task.description.clone()),
+                    Field::styled(
+                        "Priority",
+                        task.priority.to_string(),
+                        priority_style(task.priority),
+                    ),
+                    Field::new("Age", format_time(task.age.elapsed())),
+                    Field::new(
+                        "Created at",
+                        convert_utc_to_local(task.created_at, time_format),
+                    ),
+                    Field::new(
+                        "Modified at",
+                        convert_utc_to_local(task.modified_at, time_format),
+                    ),
+                    Field::new("Time logged", task.total_time_logged().to_string()),
+                    Field::new("Tags", format_tags(&task.tags)),
+                    Field::new("Blocked by", format_blocked_by(app.blocking_descriptions(task.id))),
                 ],
             )
             .block(details_block);
@@ -120,6 +161,10 @@ pub fn build_ui<B: Backend>(f: &mut Frame<B>, app: &mut Hourglass) {
         View::Task(action) => match action {
             Action::Add => title.push_str(" - Add task"),
             Action::Update => title.push_str(" - Update task"),
+            Action::Track => title.push_str(" - Log time (e.g. 1H30M)"),
+            Action::Tag => title.push_str(" - Toggle tag"),
+            Action::Filter => title.push_str(" - Filter by tag"),
+            Action::Dependency => title.push_str(" - Toggle dependency (enter task id)"),
             _ => {}
         },
         View::Issues => {}
@@ -156,6 +201,28 @@ fn format_time(time: Duration) -> String {
     format!("{}s", sec)
 }
 
+fn format_tags(tags: &HashSet<String>) -> String {
+    if tags.is_empty() {
+        return String::from("-");
+    }
+
+    let mut tags: Vec<&String> = tags.iter().collect();
+    tags.sort();
+
+    tags.into_iter()
+        .map(|t| t.as_str())
+        .collect::<Vec<&str>>()
+        .join(", ")
+}
+
+fn format_blocked_by(descriptions: Vec<String>) -> String {
+    if descriptions.is_empty() {
+        return String::from("-");
+    }
+
+    descriptions.join(", ")
+}
+
 fn convert_utc_to_local(utc_time: DateTime<Utc>, time_format: &str) -> String {
     let local_time: DateTime<Local> = DateTime::from(utc_time);
 
@@ -211,10 +278,7 @@ fn render_task_detail<'a>(columns: Vec<String>, fields: Vec<Field>) -> Paragraph
             value = field.value
         );
 
-        spans.push(Spans::from(Span::styled(
-            field_text,
-            Style::default().fg(Color::Red),
-        )));
+        spans.push(Spans::from(Span::styled(field_text, field.style)));
     }
 
     // ====================== END COLUMN FIELDS ========================
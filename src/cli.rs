@@ -0,0 +1,23 @@
+use clap::{Parser, Subcommand};
+
+/// A terminal task tracker. Running with no subcommand launches the TUI.
+#[derive(Parser)]
+#[command(name = "hourglass")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Add a new task
+    Add { description: String },
+    /// List all tasks
+    List,
+    /// Mark a task as done
+    Done { id: i32 },
+    /// Remove a task
+    Rm { id: i32 },
+    /// Log time against a task, e.g. `1H30M`
+    Track { id: i32, duration: String },
+}
@@ -1,14 +1,22 @@
+use clap::Parser;
 use std::io;
 mod app;
+mod cli;
 
 use app::Hourglass;
+use cli::Cli;
 
 fn main() -> io::Result<()> {
+    let cli = Cli::parse();
+
     let mut hourglass = Hourglass::new();
 
     hourglass.load_tasks()?;
 
-    // Ok(())
+    if let Some(command) = cli.command {
+        return hourglass.run_command(command);
+    }
+
     let mut terminal = Hourglass::start_tui()?;
 
     let r = hourglass.run(&mut terminal);